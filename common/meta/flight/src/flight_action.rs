@@ -20,6 +20,8 @@ use common_exception::ErrorCode;
 use prost::Message;
 use tonic::Request;
 
+use crate::impls::kv_batch::BatchKVAction;
+use crate::impls::kv_batch::RangeKVReq;
 use crate::impls::CreateDatabaseAction;
 use crate::impls::CreateTableAction;
 use crate::impls::DropDatabaseAction;
@@ -75,6 +77,8 @@ pub enum MetaFlightAction {
     GetKV(GetKVAction),
     MGetKV(MGetKVAction),
     PrefixListKV(PrefixListReq),
+    BatchKV(BatchKVAction),
+    RangeKV(RangeKVReq),
 }
 
 /// Try convert tonic::Request<Action> to DoActionAction.
@@ -82,6 +86,11 @@ impl TryInto<MetaFlightAction> for Request<Action> {
     type Error = tonic::Status;
 
     fn try_into(self) -> Result<MetaFlightAction, Self::Error> {
+        // Mutual TLS is enforced at the transport layer: when the server is
+        // configured with a `client_ca_root` tonic rejects a peer that presents
+        // no (or an untrusted) certificate during the handshake, long before the
+        // request reaches this decode path. No application-level certificate
+        // check is needed or possible here.
         let action = self.into_inner();
         let mut buf = Cursor::new(&action.body);
 
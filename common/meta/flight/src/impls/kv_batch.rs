@@ -0,0 +1,369 @@
+// Copyright 2021 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_exception::ErrorCode;
+use common_meta_types::KVMeta;
+use common_meta_types::KVValue;
+use common_meta_types::MatchSeq;
+use common_meta_types::SeqV;
+
+use crate::flight_action::RequestFor;
+use crate::MetaFlightAction;
+
+/// A single mutation in a [`BatchKVAction`].
+///
+/// Each op carries the key it targets and the expected seq version so the whole
+/// batch can be applied atomically with optimistic concurrency: if any op's
+/// [`MatchSeq`] does not match the stored seq the batch is rejected as a whole.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub enum BatchKVOp {
+    /// Insert or overwrite `key` with `value`, conditioned on `seq`.
+    Upsert {
+        key: String,
+        seq: MatchSeq,
+        value: Vec<u8>,
+        value_meta: Option<KVMeta>,
+    },
+    /// Delete `key`, conditioned on `seq`.
+    Delete { key: String, seq: MatchSeq },
+}
+
+/// Apply an ordered list of KV mutations atomically in one Flight round trip.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct BatchKVAction {
+    pub ops: Vec<BatchKVOp>,
+}
+
+/// The per-op outcome of a [`BatchKVAction`], in the same order as the request.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct BatchKVReply {
+    pub results: Vec<Option<SeqV<KVValue>>>,
+}
+
+action_declare!(BatchKVAction, BatchKVReply, MetaFlightAction::BatchKV);
+
+/// A bounded, ordered range scan over the KV space.
+///
+/// `start`/`end` bound the scan (half-open, `[start, end)`), `prefix` further
+/// restricts it to keys under a common prefix, `limit` caps the number of
+/// returned entries and `reverse` walks the range from `end` towards `start`.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct RangeKVReq {
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub prefix: Option<String>,
+    pub limit: Option<usize>,
+    pub reverse: bool,
+}
+
+/// A page of range-scan entries plus a continuation token.
+///
+/// `continuation` is `Some(key)` when the scan stopped at `limit` before
+/// exhausting the range; feeding it back as the next request's `start` (or
+/// `end` when `reverse`) resumes the scan where it left off.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct RangeKVReply {
+    pub entries: Vec<(String, SeqV<KVValue>)>,
+    pub continuation: Option<String>,
+}
+
+action_declare!(RangeKVReq, RangeKVReply, MetaFlightAction::RangeKV);
+
+/// The slice of the meta KV state machine that the batch and range actions
+/// drive.
+///
+/// The meta service implements this over its raft state machine; the batch and
+/// range semantics below are written purely in terms of it so they can be unit
+/// tested against an in-memory map. All mutations and reads here go through a
+/// single exclusive borrow, which is what makes a [`BatchKVAction`] atomic: the
+/// whole batch is validated and applied without yielding the state machine lock.
+pub trait KVApply {
+    /// The current entry for `key`, if any.
+    fn get(&self, key: &str) -> Option<SeqV<KVValue>>;
+    /// Insert or overwrite `key`, returning the stored entry with its new seq.
+    fn set(&mut self, key: &str, value: Vec<u8>, value_meta: Option<KVMeta>) -> SeqV<KVValue>;
+    /// Remove `key` if present.
+    fn remove(&mut self, key: &str);
+    /// Entries whose key falls in the half-open range `[start, end)`, ordered
+    /// ascending by key. An open bound (`None`) is unbounded on that side.
+    fn range(&self, start: Option<&str>, end: Option<&str>) -> Vec<(String, SeqV<KVValue>)>;
+}
+
+/// Whether a stored seq satisfies a [`MatchSeq`] condition.
+///
+/// A missing key has seq `0`, so `Exact(0)` asserts absence and `GE(1)` asserts
+/// presence, matching the single-shot `UpsertKV` conditioning semantics.
+fn seq_matches(cond: &MatchSeq, current: u64) -> bool {
+    match cond {
+        MatchSeq::Any => true,
+        MatchSeq::Exact(seq) => current == *seq,
+        MatchSeq::GE(seq) => current >= *seq,
+    }
+}
+
+impl BatchKVAction {
+    /// Apply every op atomically against `store`.
+    ///
+    /// The batch is all-or-nothing: every op's [`MatchSeq`] is checked against
+    /// the current state first, and if any op's condition fails the whole batch
+    /// is rejected without mutating anything. Only once all conditions hold are
+    /// the mutations applied, in request order, and the per-op outcomes returned
+    /// in the same order.
+    pub fn apply(&self, store: &mut impl KVApply) -> Result<BatchKVReply, ErrorCode> {
+        for op in &self.ops {
+            let (key, seq) = match op {
+                BatchKVOp::Upsert { key, seq, .. } => (key, seq),
+                BatchKVOp::Delete { key, seq } => (key, seq),
+            };
+            let current = store.get(key).map(|v| v.seq).unwrap_or(0);
+            if !seq_matches(seq, current) {
+                return Err(ErrorCode::MetaClientError(format!(
+                    "batch rejected: key {} expected seq {:?}, found {}",
+                    key, seq, current
+                )));
+            }
+        }
+
+        let mut results = Vec::with_capacity(self.ops.len());
+        for op in &self.ops {
+            match op {
+                BatchKVOp::Upsert {
+                    key,
+                    value,
+                    value_meta,
+                    ..
+                } => {
+                    let stored = store.set(key, value.clone(), value_meta.clone());
+                    results.push(Some(stored));
+                }
+                BatchKVOp::Delete { key, .. } => {
+                    store.remove(key);
+                    results.push(None);
+                }
+            }
+        }
+        Ok(BatchKVReply { results })
+    }
+}
+
+impl RangeKVReq {
+    /// Execute the bounded range scan against `store`.
+    ///
+    /// The scan first narrows `store.range(start, end)` to keys under `prefix`,
+    /// walks it in ascending or, when `reverse`, descending key order, and stops
+    /// after `limit` entries. If the limit cut the range short a continuation
+    /// token is handed back; feeding it as the next request's `start` resumes a
+    /// forward scan, and as the next request's `end` resumes a reverse scan,
+    /// exactly where this page left off with no key dropped or repeated.
+    ///
+    /// Because `start` is inclusive but `end` is exclusive, the token is picked
+    /// to suit the side it is fed back on: a forward token is the first key *not*
+    /// returned (inclusive `start`), while a reverse token is the last key that
+    /// *was* returned (exclusive `end`), so the key just beyond it begins the
+    /// next page.
+    pub fn scan(&self, store: &impl KVApply) -> RangeKVReply {
+        let mut entries = store.range(self.start.as_deref(), self.end.as_deref());
+        if let Some(prefix) = &self.prefix {
+            entries.retain(|(k, _)| k.starts_with(prefix));
+        }
+        if self.reverse {
+            entries.reverse();
+        }
+
+        match self.limit {
+            Some(limit) if entries.len() > limit => {
+                let continuation = if self.reverse {
+                    // Exclusive `end` for the next reverse page: the last key we
+                    // returned, so `[.., last_returned)` covers the keys below it.
+                    Some(entries[limit - 1].0.clone())
+                } else {
+                    // Inclusive `start` for the next forward page: the first key
+                    // we did not return.
+                    Some(entries[limit].0.clone())
+                };
+                entries.truncate(limit);
+                RangeKVReply {
+                    entries,
+                    continuation,
+                }
+            }
+            _ => RangeKVReply {
+                entries,
+                continuation: None,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct MemKV {
+        seq: u64,
+        map: BTreeMap<String, SeqV<KVValue>>,
+    }
+
+    impl KVApply for MemKV {
+        fn get(&self, key: &str) -> Option<SeqV<KVValue>> {
+            self.map.get(key).cloned()
+        }
+
+        fn set(&mut self, key: &str, value: Vec<u8>, value_meta: Option<KVMeta>) -> SeqV<KVValue> {
+            self.seq += 1;
+            let sv = SeqV::new(self.seq, KVValue {
+                meta: value_meta,
+                value,
+            });
+            self.map.insert(key.to_string(), sv.clone());
+            sv
+        }
+
+        fn remove(&mut self, key: &str) {
+            self.map.remove(key);
+        }
+
+        fn range(&self, start: Option<&str>, end: Option<&str>) -> Vec<(String, SeqV<KVValue>)> {
+            self.map
+                .iter()
+                .filter(|(k, _)| start.map_or(true, |s| k.as_str() >= s))
+                .filter(|(k, _)| end.map_or(true, |e| k.as_str() < e))
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_batch_kv_atomic() {
+        let mut store = MemKV::default();
+        store.set("a", b"1".to_vec(), None);
+
+        // A batch whose second op fails its seq check must leave the store
+        // untouched, including the first op that would otherwise have applied.
+        let batch = BatchKVAction {
+            ops: vec![
+                BatchKVOp::Upsert {
+                    key: "b".to_string(),
+                    seq: MatchSeq::Any,
+                    value: b"2".to_vec(),
+                    value_meta: None,
+                },
+                BatchKVOp::Delete {
+                    key: "a".to_string(),
+                    seq: MatchSeq::Exact(999),
+                },
+            ],
+        };
+        assert!(batch.apply(&mut store).is_err());
+        assert!(store.get("b").is_none());
+        assert!(store.get("a").is_some());
+
+        // With matching conditions the whole batch applies in order.
+        let ok = BatchKVAction {
+            ops: vec![
+                BatchKVOp::Upsert {
+                    key: "b".to_string(),
+                    seq: MatchSeq::Exact(0),
+                    value: b"2".to_vec(),
+                    value_meta: None,
+                },
+                BatchKVOp::Delete {
+                    key: "a".to_string(),
+                    seq: MatchSeq::Any,
+                },
+            ],
+        };
+        let reply = ok.apply(&mut store).unwrap();
+        assert_eq!(reply.results.len(), 2);
+        assert!(reply.results[0].is_some());
+        assert!(reply.results[1].is_none());
+        assert!(store.get("a").is_none());
+        assert_eq!(store.get("b").unwrap().data.value, b"2".to_vec());
+    }
+
+    #[test]
+    fn test_range_kv_limit_and_reverse() {
+        let mut store = MemKV::default();
+        for k in ["k1", "k2", "k3", "k4"] {
+            store.set(k, k.as_bytes().to_vec(), None);
+        }
+
+        // A limit shorter than the range returns a continuation token pointing at
+        // the first key not yet returned.
+        let page = RangeKVReq {
+            start: Some("k1".to_string()),
+            end: None,
+            prefix: Some("k".to_string()),
+            limit: Some(2),
+            reverse: false,
+        }
+        .scan(&store);
+        assert_eq!(page.entries.iter().map(|(k, _)| k.as_str()).collect::<Vec<_>>(), vec![
+            "k1", "k2"
+        ]);
+        assert_eq!(page.continuation, Some("k3".to_string()));
+
+        // Resuming from the token yields the rest with no further continuation.
+        let rest = RangeKVReq {
+            start: page.continuation,
+            end: None,
+            prefix: Some("k".to_string()),
+            limit: Some(2),
+            reverse: false,
+        }
+        .scan(&store);
+        assert_eq!(rest.entries.iter().map(|(k, _)| k.as_str()).collect::<Vec<_>>(), vec![
+            "k3", "k4"
+        ]);
+        assert_eq!(rest.continuation, None);
+
+        // Reverse walks the same range from the high end. The continuation is
+        // the last key returned, fed back as an exclusive `end`.
+        let back = RangeKVReq {
+            start: None,
+            end: None,
+            prefix: Some("k".to_string()),
+            limit: Some(2),
+            reverse: true,
+        }
+        .scan(&store);
+        assert_eq!(back.entries.iter().map(|(k, _)| k.as_str()).collect::<Vec<_>>(), vec![
+            "k4", "k3"
+        ]);
+        assert_eq!(back.continuation, Some("k3".to_string()));
+
+        // Resuming the reverse scan from the token must yield the remaining keys
+        // with none dropped, reconstructing the full descending set.
+        let back_rest = RangeKVReq {
+            start: None,
+            end: back.continuation,
+            prefix: Some("k".to_string()),
+            limit: Some(2),
+            reverse: true,
+        }
+        .scan(&store);
+        assert_eq!(
+            back_rest.entries.iter().map(|(k, _)| k.as_str()).collect::<Vec<_>>(),
+            vec!["k2", "k1"]
+        );
+        assert_eq!(back_rest.continuation, None);
+
+        let mut full: Vec<&str> = back.entries.iter().map(|(k, _)| k.as_str()).collect();
+        full.extend(back_rest.entries.iter().map(|(k, _)| k.as_str()));
+        assert_eq!(full, vec!["k4", "k3", "k2", "k1"]);
+    }
+}
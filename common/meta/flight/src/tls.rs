@@ -0,0 +1,136 @@
+// Copyright 2021 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_exception::ErrorCode;
+use common_exception::Result;
+use tonic::transport::Certificate;
+use tonic::transport::Channel;
+use tonic::transport::ClientTlsConfig;
+use tonic::transport::Endpoint;
+use tonic::transport::Identity;
+use tonic::transport::Server;
+use tonic::transport::ServerTlsConfig;
+
+/// Transport security for the meta Flight channel.
+///
+/// Every path is optional so that an unconfigured deployment keeps the
+/// plaintext behaviour. When `cert` and `key` are set the server presents its
+/// certificate; when `ca` is set a client validates the server certificate
+/// against it. The server reads `ca` only when `require_client_auth` is set, and
+/// then demands a client certificate signed by that CA (mutual TLS).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MetaFlightTlsConfig {
+    /// PEM server/client certificate path.
+    pub cert: String,
+    /// PEM private key path.
+    pub key: String,
+    /// PEM CA certificate path used to validate the peer.
+    pub ca: String,
+    /// Require the client to present a certificate (server side, mutual TLS).
+    pub require_client_auth: bool,
+}
+
+/// Configuration for the meta Flight endpoint: its address and transport
+/// security.
+///
+/// The [`tls`](MetaFlightConfig::tls) block secures the server through
+/// [`MetaFlightTlsConfig::server_builder`] and clients through
+/// [`MetaFlightTlsConfig::connect`]. The struct implements no deserialization,
+/// so it is not read from a config file; it is built in code.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MetaFlightConfig {
+    /// `host:port` the meta Flight service listens on / clients dial.
+    pub address: String,
+    /// Transport security for the channel; empty paths keep it plaintext.
+    pub tls: MetaFlightTlsConfig,
+}
+
+impl MetaFlightTlsConfig {
+    /// Whether both a certificate and a key are configured; a CA alone does not
+    /// count.
+    pub fn enabled(&self) -> bool {
+        !self.cert.is_empty() && !self.key.is_empty()
+    }
+
+    /// A `tonic` server builder with the meta channel's TLS configured.
+    ///
+    /// When no certificate material is set the builder is returned untouched so
+    /// an unconfigured deployment keeps serving plaintext.
+    pub fn server_builder(&self) -> Result<Server> {
+        let mut builder = Server::builder();
+        if self.enabled() {
+            builder = builder.tls_config(self.server_config()?).map_err(|e| {
+                ErrorCode::TLSConfigurationFailure(format!("server tls_config: {}", e))
+            })?;
+        }
+        Ok(builder)
+    }
+
+    /// Connect a client `Channel` to `endpoint`, negotiating TLS when a CA or
+    /// client identity is configured.
+    pub async fn connect(&self, endpoint: Endpoint) -> Result<Channel> {
+        let endpoint = if self.enabled() || !self.ca.is_empty() {
+            endpoint.tls_config(self.client_config()?).map_err(|e| {
+                ErrorCode::TLSConfigurationFailure(format!("client tls_config: {}", e))
+            })?
+        } else {
+            endpoint
+        };
+        endpoint
+            .connect()
+            .await
+            .map_err(|e| ErrorCode::CannotConnectNode(format!("connect meta service: {}", e)))
+    }
+
+    /// Build the server-side TLS configuration, wiring mutual TLS when a CA is
+    /// configured alongside `require_client_auth`.
+    pub fn server_config(&self) -> Result<ServerTlsConfig> {
+        let cert = std::fs::read(&self.cert)
+            .map_err(|e| ErrorCode::TLSConfigurationFailure(format!("read cert: {}", e)))?;
+        let key = std::fs::read(&self.key)
+            .map_err(|e| ErrorCode::TLSConfigurationFailure(format!("read key: {}", e)))?;
+
+        let mut config = ServerTlsConfig::new().identity(Identity::from_pem(cert, key));
+        if self.require_client_auth {
+            if self.ca.is_empty() {
+                return Err(ErrorCode::TLSConfigurationFailure(
+                    "require_client_auth needs a CA certificate",
+                ));
+            }
+            let ca = std::fs::read(&self.ca)
+                .map_err(|e| ErrorCode::TLSConfigurationFailure(format!("read ca: {}", e)))?;
+            config = config.client_ca_root(Certificate::from_pem(ca));
+        }
+        Ok(config)
+    }
+
+    /// Build the client-side TLS configuration, presenting a client certificate
+    /// for mutual TLS when both `cert` and `key` are set.
+    pub fn client_config(&self) -> Result<ClientTlsConfig> {
+        let mut config = ClientTlsConfig::new();
+        if !self.ca.is_empty() {
+            let ca = std::fs::read(&self.ca)
+                .map_err(|e| ErrorCode::TLSConfigurationFailure(format!("read ca: {}", e)))?;
+            config = config.ca_certificate(Certificate::from_pem(ca));
+        }
+        if self.enabled() {
+            let cert = std::fs::read(&self.cert)
+                .map_err(|e| ErrorCode::TLSConfigurationFailure(format!("read cert: {}", e)))?;
+            let key = std::fs::read(&self.key)
+                .map_err(|e| ErrorCode::TLSConfigurationFailure(format!("read key: {}", e)))?;
+            config = config.identity(Identity::from_pem(cert, key));
+        }
+        Ok(config)
+    }
+}
@@ -0,0 +1,112 @@
+// Copyright 2021 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+/// A user-defined function registered through `CREATE FUNCTION`.
+///
+/// A function is no longer necessarily a scalar expression: [`UDFDefinition`]
+/// distinguishes the three kinds the planner can resolve during
+/// `PlanParser::parse` — a scalar expression, an aggregate with explicit state
+/// transitions, and a set-returning table function.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct UserDefinedFunction {
+    pub name: String,
+    pub description: String,
+    pub definition: UDFDefinition,
+}
+
+/// The body of a [`UserDefinedFunction`], one variant per function kind.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq)]
+pub enum UDFDefinition {
+    /// A scalar expression evaluated per row, e.g. `not(isnull(@0))`.
+    Scalar { expr: String },
+
+    /// An aggregate function driven by the usual state machine: `init` seeds the
+    /// accumulator, `accumulate` folds each input row in, `merge` combines two
+    /// partial states across partitions and `finalize` produces the result.
+    Aggregate {
+        init: String,
+        accumulate: String,
+        merge: String,
+        finalize: String,
+    },
+
+    /// A table function that expands into a stream of rows, `body` describing the
+    /// set-returning expression.
+    Table { body: String },
+}
+
+impl UserDefinedFunction {
+    /// Build a scalar-expression UDF — the original `CREATE FUNCTION ... = 'expr'`
+    /// form.
+    pub fn new_scalar(
+        name: impl Into<String>,
+        expr: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        UserDefinedFunction {
+            name: name.into(),
+            description: description.into(),
+            definition: UDFDefinition::Scalar { expr: expr.into() },
+        }
+    }
+
+    /// Build an aggregate UDF from its `init`/`accumulate`/`merge`/`finalize`
+    /// state-machine expressions.
+    pub fn new_aggregate(
+        name: impl Into<String>,
+        init: impl Into<String>,
+        accumulate: impl Into<String>,
+        merge: impl Into<String>,
+        finalize: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        UserDefinedFunction {
+            name: name.into(),
+            description: description.into(),
+            definition: UDFDefinition::Aggregate {
+                init: init.into(),
+                accumulate: accumulate.into(),
+                merge: merge.into(),
+                finalize: finalize.into(),
+            },
+        }
+    }
+
+    /// Build a set-returning table UDF from its body expression.
+    pub fn new_table(
+        name: impl Into<String>,
+        body: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        UserDefinedFunction {
+            name: name.into(),
+            description: description.into(),
+            definition: UDFDefinition::Table { body: body.into() },
+        }
+    }
+}
+
+impl UDFDefinition {
+    /// The scalar expression of this function, if it is a scalar UDF.
+    ///
+    /// Scalar call sites read the expression through this accessor
+    /// (`udf.definition.as_scalar()`); it returns `None` for the aggregate and
+    /// table kinds, which are reached by matching on [`UDFDefinition`].
+    pub fn as_scalar(&self) -> Option<&str> {
+        match self {
+            UDFDefinition::Scalar { expr } => Some(expr.as_str()),
+            _ => None,
+        }
+    }
+}
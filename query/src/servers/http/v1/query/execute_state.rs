@@ -16,6 +16,12 @@ use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
 
+use common_arrow::arrow::chunk::Chunk;
+use common_arrow::arrow::io::flight::serialize_batch;
+use common_arrow::arrow::io::flight::serialize_schema;
+use common_arrow::arrow::io::ipc::write::default_ipc_fields;
+use common_arrow::arrow::io::ipc::write::WriteOptions;
+use common_arrow::arrow_flight::FlightData;
 use common_base::tokio;
 use common_base::tokio::sync::mpsc;
 use common_base::tokio::sync::RwLock;
@@ -25,10 +31,14 @@ use common_datablocks::DataBlock;
 use common_datavalues::DataSchemaRef;
 use common_exception::ErrorCode;
 use common_exception::Result;
+use common_streams::SendableDataBlockStream;
 use common_tracing::tracing;
+use common_tracing::tracing::Instrument;
+use common_tracing::tracing::Span;
 use futures::StreamExt;
 use serde::Deserialize;
 use serde::Serialize;
+use uuid::Uuid;
 use ExecuteState::*;
 
 use crate::interpreters::Interpreter;
@@ -109,10 +119,13 @@ impl Executor {
             if kill {
                 r.session.force_kill_query();
             }
-            // Write Finish to query log table.
+            // Write Finish to query log table. Parent the span on the per-query
+            // root so `finish` sits as a sibling of parse/build/start/execute
+            // rather than nesting under the execution task's span.
             let _ = r
                 .interpreter
                 .finish()
+                .instrument(tracing::info_span!(parent: &r.query_span, "interpreter_finish"))
                 .await
                 .map_err(|e| tracing::error!("interpreter.finish error: {:?}", e));
             guard.state = Stopped(ExecuteStopped {
@@ -124,6 +137,139 @@ impl Executor {
     }
 }
 
+/// One page of results handed back by [`ResultCursor::next_page`].
+///
+/// A page carries up to the requested number of blocks together with the
+/// query's live [`ProgressValues`] and `elapsed` time, so a polling client sees
+/// the same progress information on every fetch. `next` is the token to pass
+/// back for the following page, or `None` once the buffer is drained and
+/// execution has stopped.
+pub(crate) struct ResultPage {
+    pub(crate) blocks: Vec<DataBlock>,
+    pub(crate) next: Option<String>,
+    pub(crate) progress: Option<ProgressValues>,
+    pub(crate) elapsed: Duration,
+}
+
+/// A resumable, poll-based view over a running query's results.
+///
+/// Blocks are fed through a bounded channel, so the producer task pauses block
+/// consumption whenever the client falls behind (backpressure). Clients call
+/// [`ResultCursor::next_page`] to pull the next page of up to `n` blocks (pages
+/// are cut at block boundaries, not at a row count); after `ExecuteState`
+/// reaches `Stopped` the cursor keeps serving whatever remains buffered before
+/// reporting the end of the stream.
+pub(crate) struct ResultCursor {
+    executor: ExecutorRef,
+    block_rx: mpsc::Receiver<DataBlock>,
+    /// Cumulative number of blocks handed back so far, i.e. the offset that the
+    /// next page starts at. This is the value encoded in the page token.
+    delivered: usize,
+    /// One block read ahead of the client so the last data-bearing page can be
+    /// recognised without forcing an extra empty poll.
+    pending: Option<DataBlock>,
+}
+
+impl ResultCursor {
+    /// Fetch the next page of at most `n` blocks, resuming from `token`.
+    ///
+    /// `token` is the offset returned as a previous page's `next`; it must match
+    /// the cursor's current position, which guards against duplicate or
+    /// out-of-order polls. `None` starts from the beginning.
+    ///
+    /// The call waits for at least one block so a poll never returns an empty
+    /// page while the query is still producing, then greedily drains anything
+    /// already buffered up to `n`. It then peeks one block ahead: if nothing
+    /// more will arrive — the producer has stopped and the buffer is drained, or
+    /// the channel has closed — the returned page is the last one and its `next`
+    /// token is `None`, so the final data-bearing page reports the end directly
+    /// rather than forcing one more poll that returns an empty page.
+    pub(crate) async fn next_page(&mut self, token: Option<&str>, n: usize) -> Result<ResultPage> {
+        let offset: usize = match token {
+            Some(token) => token
+                .parse()
+                .map_err(|_| ErrorCode::BadArguments(format!("invalid page token: {}", token)))?,
+            None => 0,
+        };
+        if offset != self.delivered {
+            return Err(ErrorCode::BadArguments(format!(
+                "stale page token {}: cursor is at {}",
+                offset, self.delivered
+            )));
+        }
+
+        let mut blocks = Vec::with_capacity(n);
+        if let Some(block) = self.pending.take() {
+            blocks.push(block);
+        }
+        if blocks.is_empty() {
+            if let Some(block) = self.block_rx.recv().await {
+                blocks.push(block);
+            }
+        }
+        while blocks.len() < n {
+            match self.block_rx.try_recv() {
+                Ok(block) => blocks.push(block),
+                Err(_) => break,
+            }
+        }
+
+        let guard = self.executor.read().await;
+        let progress = guard.get_progress();
+        let elapsed = guard.elapsed();
+        drop(guard);
+
+        // Read one block ahead to decide whether a next page exists. The
+        // look-ahead happens before `stopped` is sampled: otherwise a query that
+        // finishes in the window between the two reads would be seen as "still
+        // running" and wrongly report `next = Some(..)` for a terminal page.
+        let has_more = if blocks.is_empty() {
+            false
+        } else {
+            match self.block_rx.try_recv() {
+                Ok(block) => {
+                    self.pending = Some(block);
+                    true
+                }
+                Err(mpsc::error::TryRecvError::Disconnected) => false,
+                Err(mpsc::error::TryRecvError::Empty) => {
+                    // Buffer empty right now. Once the producer has stopped every
+                    // block it will ever send is already buffered, so re-check the
+                    // channel after observing `stopped`: a still-running query has
+                    // a next page to poll, while a stopped one only does if a
+                    // block slipped in during the window.
+                    let stopped = matches!(self.executor.read().await.state, Stopped(_));
+                    if !stopped {
+                        true
+                    } else {
+                        match self.block_rx.try_recv() {
+                            Ok(block) => {
+                                self.pending = Some(block);
+                                true
+                            }
+                            Err(_) => false,
+                        }
+                    }
+                }
+            }
+        };
+
+        self.delivered += blocks.len();
+        let next = if has_more {
+            Some(self.delivered.to_string())
+        } else {
+            None
+        };
+
+        Ok(ResultPage {
+            blocks,
+            next,
+            progress,
+            elapsed,
+        })
+    }
+}
+
 pub struct HttpQueryHandle {
     pub abort_sender: mpsc::Sender<()>,
 }
@@ -143,14 +289,30 @@ pub(crate) struct ExecuteRunning {
     // mainly used to get progress for now
     context: Arc<QueryContext>,
     interpreter: Arc<dyn Interpreter>,
+    // per-query span root, so `finish` (emitted from `stop`) nests under it
+    query_span: Span,
+}
+
+/// The session/user/parse/interpreter setup shared by every result transport.
+///
+/// Both the JSON/HTTP streaming path and the Arrow Flight path need exactly the
+/// same prologue — create the session, resolve the user, parse the statement,
+/// build and `start()` the interpreter and stand up the `Executor`/abort
+/// channel — so it lives here once rather than being copy-pasted per transport.
+struct PreparedQuery {
+    executor: ExecutorRef,
+    schema: DataSchemaRef,
+    data_stream: SendableDataBlockStream,
+    abort_rx: mpsc::Receiver<()>,
+    context: Arc<QueryContext>,
 }
 
 impl ExecuteState {
-    pub(crate) async fn try_create(
+    async fn prepare(
         request: &HttpQueryRequest,
         session_manager: &Arc<SessionManager>,
-        block_tx: mpsc::Sender<DataBlock>,
-    ) -> Result<(ExecutorRef, DataSchemaRef)> {
+        query_span: Span,
+    ) -> Result<PreparedQuery> {
         let sql = &request.sql;
         let session = session_manager.create_session("http-statement")?;
         let context = session.create_context().await?;
@@ -158,6 +320,7 @@ impl ExecuteState {
             context.set_current_database(db.clone()).await?;
         };
         context.attach_query_str(sql);
+
         let default_user = "root".to_string();
         let user_name = request.session.user.as_ref().unwrap_or(&default_user);
         let user_manager = session.get_user_manager();
@@ -169,20 +332,26 @@ impl ExecuteState {
             .await?;
         session.set_current_user(user_info);
 
-        let plan = PlanParser::parse(sql, context.clone()).await?;
+        let plan = PlanParser::parse(sql, context.clone())
+            .instrument(tracing::info_span!("parse"))
+            .await?;
         let schema = plan.schema();
 
-        let interpreter = InterpreterFactory::get(context.clone(), plan.clone())?;
+        let interpreter = {
+            let _build = tracing::info_span!("interpreter_build").entered();
+            InterpreterFactory::get(context.clone(), plan.clone())?
+        };
         // Write Start to query log table.
         let _ = interpreter
             .start()
+            .instrument(tracing::info_span!("interpreter_start"))
             .await
             .map_err(|e| tracing::error!("interpreter.start.error: {:?}", e));
 
         let data_stream = interpreter.execute(None).await?;
-        let mut data_stream = context.try_create_abortable(data_stream)?;
+        let data_stream = context.try_create_abortable(data_stream)?;
 
-        let (abort_tx, mut abort_rx) = mpsc::channel(2);
+        let (abort_tx, abort_rx) = mpsc::channel(2);
         context.attach_http_query(HttpQueryHandle {
             abort_sender: abort_tx,
         });
@@ -191,25 +360,175 @@ impl ExecuteState {
             session,
             context: context.clone(),
             interpreter: interpreter.clone(),
+            query_span,
         };
         let executor = Arc::new(RwLock::new(Executor {
             start_time: Instant::now(),
             state: Running(running_state),
         }));
 
+        Ok(PreparedQuery {
+            executor,
+            schema,
+            data_stream,
+            abort_rx,
+            context,
+        })
+    }
+
+    pub(crate) async fn try_create(
+        request: &HttpQueryRequest,
+        session_manager: &Arc<SessionManager>,
+        block_tx: mpsc::Sender<DataBlock>,
+    ) -> Result<(ExecutorRef, DataSchemaRef)> {
+        // Root the whole statement under a per-query span carrying the query id
+        // and the attached query string, so every child stage below forms one
+        // timed span tree instead of interleaved log lines across queries.
+        let query_id = Uuid::new_v4().to_string();
+        let query_span =
+            tracing::info_span!("http_query", query.id = %query_id, query.sql = %request.sql);
+
+        // Instrument the prologue future with the query span rather than holding
+        // an entered guard across its `.await`s: an entered guard would stay on
+        // the thread while the task is parked and bleed into concurrent queries'
+        // work, defeating the per-query span tree.
+        let PreparedQuery {
+            executor,
+            schema,
+            mut data_stream,
+            mut abort_rx,
+            context,
+        } = ExecuteState::prepare(request, session_manager, query_span.clone())
+            .instrument(query_span.clone())
+            .await?;
+
         let executor_clone = executor.clone();
+        // Streaming execution runs on its own task, so carry a child span
+        // explicitly rather than relying on the current thread's span.
+        let exec_span = tracing::info_span!(parent: &query_span, "execute");
+        let exec_context = context.clone();
+        context
+            .try_spawn(
+                async move {
+                    loop {
+                        if let Some(block_r) = data_stream.next().await {
+                            match block_r {
+                                Ok(block) => tokio::select! {
+                                    _ = block_tx.send(block) => { },
+                                    _ = abort_rx.recv() => {
+                                        Executor::stop(&executor, Err(ErrorCode::AbortedQuery("query aborted")), true).await;
+                                        break;
+                                    },
+                                },
+                                Err(err) => {
+                                    Executor::stop(&executor, Err(err), false).await;
+                                    break;
+                                }
+                            };
+                        } else {
+                            Executor::stop(&executor, Ok(()), false).await;
+                            break;
+                        }
+                    }
+                    let progress = exec_context.get_scan_progress_value();
+                    tracing::info!(
+                        scan_rows = progress.read_rows,
+                        scan_bytes = progress.read_bytes,
+                        "execute finished"
+                    );
+                    tracing::debug!("drop block sender!");
+                }
+                .instrument(exec_span),
+            )?;
+
+        Ok((executor_clone, schema))
+    }
+
+    /// Create a query whose results are consumed through a [`ResultCursor`].
+    ///
+    /// Reuses [`ExecuteState::try_create`] but hands it a bounded channel sized
+    /// at `page_buffer_size`: the producer task's `block_tx.send(...).await`
+    /// parks once the buffer is full, pausing block consumption until the client
+    /// polls the next page. The returned `ExecutorRef` still drives the same
+    /// progress/kill machinery as the streaming path.
+    pub(crate) async fn try_create_paged(
+        request: &HttpQueryRequest,
+        session_manager: &Arc<SessionManager>,
+        page_buffer_size: usize,
+    ) -> Result<(ExecutorRef, DataSchemaRef, ResultCursor)> {
+        let (block_tx, block_rx) = mpsc::channel(page_buffer_size);
+        let (executor, schema) =
+            ExecuteState::try_create(request, session_manager, block_tx).await?;
+        let cursor = ResultCursor {
+            executor: executor.clone(),
+            block_rx,
+            delivered: 0,
+            pending: None,
+        };
+        Ok((executor, schema, cursor))
+    }
+
+    /// Arrow Flight `DoGet` result transport.
+    ///
+    /// Mirrors [`ExecuteState::try_create`] but, instead of pushing buffered
+    /// `DataBlock`s through the JSON/HTTP path, converts each block to Arrow IPC
+    /// `FlightData` frames and streams them to the client: the schema message is
+    /// emitted first, followed by the dictionary and record-batch messages of
+    /// each block. The resulting query reuses the same `Executor`/`abort_sender`
+    /// machinery as an HTTP one: the returned `ExecutorRef` reports progress and
+    /// can stop it, and the `abort_sender` attached to its context aborts it.
+    ///
+    /// Each block maps to a `Vec<FlightData>` rather than a single frame: a
+    /// dictionary-encoded column emits one or more dictionary-batch messages that
+    /// must reach the client ahead of the record-batch message that references
+    /// them, otherwise the stream is undecodable.
+    pub(crate) async fn try_create_flight(
+        request: &HttpQueryRequest,
+        session_manager: &Arc<SessionManager>,
+        flight_tx: mpsc::Sender<Vec<FlightData>>,
+    ) -> Result<(ExecutorRef, DataSchemaRef)> {
+        let query_id = Uuid::new_v4().to_string();
+        let query_span =
+            tracing::info_span!("http_query", query.id = %query_id, query.sql = %request.sql);
+
+        // Instrument the prologue future with the query span rather than holding
+        // an entered guard across its `.await`s (see `try_create`).
+        let PreparedQuery {
+            executor,
+            schema,
+            mut data_stream,
+            mut abort_rx,
+            context,
+        } = ExecuteState::prepare(request, session_manager, query_span.clone())
+            .instrument(query_span.clone())
+            .await?;
+
+        // The schema message always precedes any record-batch message.
+        let arrow_schema = schema.to_arrow();
+        let ipc_fields = default_ipc_fields(&arrow_schema.fields);
+        let schema_data = serialize_schema(&arrow_schema, Some(&ipc_fields));
+
+        let executor_clone = executor.clone();
+        let exec_span = tracing::info_span!(parent: &query_span, "execute");
         context
             .try_spawn(async move {
+                if flight_tx.send(vec![schema_data]).await.is_err() {
+                    Executor::stop(&executor, Ok(()), false).await;
+                    return;
+                }
                 loop {
                     if let Some(block_r) = data_stream.next().await {
                         match block_r {
-                            Ok(block) => tokio::select! {
-                                _ = block_tx.send(block) => { },
-                                _ = abort_rx.recv() => {
-                                    Executor::stop(&executor, Err(ErrorCode::AbortedQuery("query aborted")), true).await;
-                                    break;
-                                },
-                            },
+                            Ok(block) => {
+                                let flight_data = block_to_flight_data(&block, &ipc_fields);
+                                tokio::select! {
+                                    _ = flight_tx.send(flight_data) => { },
+                                    _ = abort_rx.recv() => {
+                                        Executor::stop(&executor, Err(ErrorCode::AbortedQuery("query aborted")), true).await;
+                                        break;
+                                    },
+                                }
+                            }
                             Err(err) => {
                                 Executor::stop(&executor, Err(err), false).await;
                                 break;
@@ -220,9 +539,32 @@ impl ExecuteState {
                         break;
                     }
                 }
-                tracing::debug!("drop block sender!");
-            })?;
+                tracing::debug!("drop flight sender!");
+            }
+            .instrument(exec_span))?;
 
         Ok((executor_clone, schema))
     }
 }
+
+/// Serialize a single `DataBlock` into its Arrow IPC `FlightData` frames.
+///
+/// Returns the dictionary-batch messages followed by the record-batch message,
+/// in send order: the client must receive the dictionaries before the values
+/// frame that references them. The `ipc_fields` must be the ones derived from
+/// the query schema so the frames line up with the schema message sent ahead.
+fn block_to_flight_data(
+    block: &DataBlock,
+    ipc_fields: &[common_arrow::arrow::io::ipc::IpcField],
+) -> Vec<FlightData> {
+    let columns = block
+        .columns()
+        .iter()
+        .map(|column| column.as_arrow_array())
+        .collect::<Vec<_>>();
+    let chunk = Chunk::new(columns);
+    let (dicts, values) = serialize_batch(&chunk, ipc_fields, &WriteOptions { compression: None });
+    let mut frames = dicts;
+    frames.push(values);
+    frames
+}
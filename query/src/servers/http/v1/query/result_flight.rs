@@ -0,0 +1,156 @@
+// Copyright 2021 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::pin::Pin;
+use std::sync::Arc;
+
+use common_arrow::arrow_flight::flight_service_server::FlightService;
+use common_arrow::arrow_flight::Action;
+use common_arrow::arrow_flight::ActionType;
+use common_arrow::arrow_flight::Criteria;
+use common_arrow::arrow_flight::Empty;
+use common_arrow::arrow_flight::FlightData;
+use common_arrow::arrow_flight::FlightDescriptor;
+use common_arrow::arrow_flight::FlightInfo;
+use common_arrow::arrow_flight::HandshakeRequest;
+use common_arrow::arrow_flight::HandshakeResponse;
+use common_arrow::arrow_flight::PutResult;
+use common_arrow::arrow_flight::SchemaResult;
+use common_arrow::arrow_flight::Ticket;
+use common_base::tokio::sync::mpsc;
+use futures::Stream;
+use futures::StreamExt;
+use tonic::Request;
+use tonic::Response;
+use tonic::Status;
+use tonic::Streaming;
+
+use super::execute_state::ExecuteState;
+use super::execute_state::HttpQueryRequest;
+use crate::sessions::SessionManager;
+
+/// Arrow Flight service exposing query results over `DoGet`.
+///
+/// A client issues `DoGet` with a [`Ticket`] whose bytes are the JSON-encoded
+/// [`HttpQueryRequest`]. The service spins up the same streaming producer as the
+/// HTTP path ([`ExecuteState::try_create_flight`]) and hands the client the IPC
+/// `FlightData` frames the producer emits — schema message first, then each
+/// block's dictionary and record-batch messages. The query runs behind the same
+/// `Executor`/`abort_sender` machinery as an HTTP statement, so it can be
+/// aborted through the `abort_sender` attached to its context. `do_get` does not
+/// keep the returned `ExecutorRef` and gives the client no query id, so a
+/// dropped stream cannot be resumed.
+pub struct FlightResultService {
+    session_manager: Arc<SessionManager>,
+}
+
+impl FlightResultService {
+    pub fn create(session_manager: Arc<SessionManager>) -> Self {
+        FlightResultService { session_manager }
+    }
+}
+
+type FlightStream<T> = Pin<Box<dyn Stream<Item = Result<T, Status>> + Send + 'static>>;
+
+#[tonic::async_trait]
+impl FlightService for FlightResultService {
+    type HandshakeStream = FlightStream<HandshakeResponse>;
+    type ListFlightsStream = FlightStream<FlightInfo>;
+    type DoGetStream = FlightStream<FlightData>;
+    type DoPutStream = FlightStream<PutResult>;
+    type DoActionStream = FlightStream<common_arrow::arrow_flight::Result>;
+    type ListActionsStream = FlightStream<ActionType>;
+    type DoExchangeStream = FlightStream<FlightData>;
+
+    async fn do_get(
+        &self,
+        request: Request<Ticket>,
+    ) -> Result<Response<Self::DoGetStream>, Status> {
+        let ticket = request.into_inner();
+        let query: HttpQueryRequest = serde_json::from_slice(&ticket.ticket)
+            .map_err(|e| Status::invalid_argument(format!("invalid ticket: {}", e)))?;
+
+        // A bounded channel gives the producer backpressure: it parks once the
+        // client falls behind, just like the paged HTTP path.
+        let (flight_tx, flight_rx) = mpsc::channel::<Vec<FlightData>>(2);
+        ExecuteState::try_create_flight(&query, &self.session_manager, flight_tx)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Flatten each block's `Vec<FlightData>` into the flat frame stream the
+        // Flight protocol expects, preserving dict-before-values order.
+        let stream = futures::stream::unfold(flight_rx, |mut rx| async move {
+            rx.recv().await.map(|frames| (frames, rx))
+        })
+        .flat_map(|frames| futures::stream::iter(frames.into_iter().map(Ok)));
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    async fn handshake(
+        &self,
+        _request: Request<Streaming<HandshakeRequest>>,
+    ) -> Result<Response<Self::HandshakeStream>, Status> {
+        Err(Status::unimplemented("handshake is not implemented"))
+    }
+
+    async fn list_flights(
+        &self,
+        _request: Request<Criteria>,
+    ) -> Result<Response<Self::ListFlightsStream>, Status> {
+        Err(Status::unimplemented("list_flights is not implemented"))
+    }
+
+    async fn get_flight_info(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        Err(Status::unimplemented("get_flight_info is not implemented"))
+    }
+
+    async fn get_schema(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<SchemaResult>, Status> {
+        Err(Status::unimplemented("get_schema is not implemented"))
+    }
+
+    async fn do_put(
+        &self,
+        _request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoPutStream>, Status> {
+        Err(Status::unimplemented("do_put is not implemented"))
+    }
+
+    async fn do_action(
+        &self,
+        _request: Request<Action>,
+    ) -> Result<Response<Self::DoActionStream>, Status> {
+        Err(Status::unimplemented("do_action is not implemented"))
+    }
+
+    async fn list_actions(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<Self::ListActionsStream>, Status> {
+        Err(Status::unimplemented("list_actions is not implemented"))
+    }
+
+    async fn do_exchange(
+        &self,
+        _request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoExchangeStream>, Status> {
+        Err(Status::unimplemented("do_exchange is not implemented"))
+    }
+}
@@ -14,6 +14,8 @@
 
 use common_base::tokio;
 use common_exception::Result;
+use common_meta_types::UDFDefinition;
+use common_meta_types::UserDefinedFunction;
 use common_planners::*;
 use databend_query::interpreters::*;
 use databend_query::sql::*;
@@ -44,7 +46,7 @@ async fn test_drop_udf_interpreter() -> Result<()> {
             .await?;
 
         assert_eq!(udf.name, "isnotempty");
-        assert_eq!(udf.definition, "not(isnull(@0))");
+        assert_eq!(udf.definition.as_scalar(), Some("not(isnull(@0))"));
         assert_eq!(udf.description, "This is a description")
     } else {
         panic!()
@@ -89,7 +91,7 @@ async fn test_drop_udf_interpreter() -> Result<()> {
             .await?;
 
         assert_eq!(udf.name, "isnotempty");
-        assert_eq!(udf.definition, "not(isnull(@0))");
+        assert_eq!(udf.definition.as_scalar(), Some("not(isnull(@0))"));
         assert_eq!(udf.description, "This is a description")
     } else {
         panic!()
@@ -104,5 +106,29 @@ async fn test_drop_udf_interpreter() -> Result<()> {
         panic!()
     }
 
+    // Aggregate and table UDFs register through the same user-manager registry
+    // as scalar UDFs and resolve back to their respective function kinds.
+    let user_manager = ctx.get_sessions_manager().get_user_manager();
+
+    let my_sum =
+        UserDefinedFunction::new_aggregate("my_sum", "0", "@state+@0", "@0+@1", "@state", "sum");
+    user_manager.add_udf(my_sum).await?;
+    let udf = user_manager.get_udf("my_sum").await?;
+    assert_eq!(udf.name, "my_sum");
+    assert_eq!(udf.definition, UDFDefinition::Aggregate {
+        init: "0".to_string(),
+        accumulate: "@state+@0".to_string(),
+        merge: "@0+@1".to_string(),
+        finalize: "@state".to_string(),
+    });
+
+    let my_range = UserDefinedFunction::new_table("my_range", "range(@0)", "range table");
+    user_manager.add_udf(my_range).await?;
+    let udf = user_manager.get_udf("my_range").await?;
+    assert_eq!(udf.name, "my_range");
+    assert_eq!(udf.definition, UDFDefinition::Table {
+        body: "range(@0)".to_string(),
+    });
+
     Ok(())
 }
\ No newline at end of file